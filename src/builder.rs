@@ -1,6 +1,6 @@
 //! Exposed Query Builder
 
-use crate::{routes, types};
+use crate::{client, routes, types};
 
 /// Builder for creating datadog API requests
 ///
@@ -15,6 +15,7 @@ use crate::{routes, types};
 ///     let mut builder = builder::Builder::new();
 ///     let (status, res) = builder.v2()
 ///         .create_new_tag_config("my.metric.name")
+///         .expect("tags route is registered for v2")
 ///         .headers(vec![
 ///             ("Accept", "application/json"),
 ///             ("Content-Type", "application/json"),
@@ -28,12 +29,22 @@ use crate::{routes, types};
 ///     println!("Response: {:?}", res);
 /// };
 /// ```
-#[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[derive(Clone, Default, Debug)]
 pub struct Builder {
     /// API Version
     pub version: types::version::ApiVersion,
     /// Request headers
     pub headers: Vec<(String, String)>,
+    /// Opt-in self-instrumentation, enabled via [`Builder::with_subscriber`]
+    health: Option<std::sync::Arc<types::health::HealthMetrics>>,
+}
+
+impl Eq for Builder {}
+
+impl PartialEq for Builder {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version && self.headers == other.headers
+    }
 }
 
 impl Builder {
@@ -52,7 +63,9 @@ impl Builder {
         }
     }
 
-    /// Initialize a tracing subscriber
+    /// Initialize a tracing subscriber, and opt in to the self-instrumentation
+    /// health metrics recorded against every route returned by this
+    /// builder's `post_*`/`get_*` methods
     pub fn with_subscriber(&mut self) -> &mut Self {
         let subscriber_builder = tracing_subscriber::fmt();
         let mut env_filter = tracing_subscriber::EnvFilter::from_default_env();
@@ -60,9 +73,70 @@ impl Builder {
         if let Err(e) = subscriber_builder.with_env_filter(env_filter).try_init() {
             println!("Failed to initialize tracing!\nError: {:?}", e)
         }
+        self.health
+            .get_or_insert_with(|| std::sync::Arc::new(types::health::HealthMetrics::new()));
         self
     }
 
+    /// Snapshots this builder's health metrics, if
+    /// [`Builder::with_subscriber`] has enabled them
+    pub fn health_snapshot(&self) -> Option<types::health::HealthSnapshot> {
+        self.health.as_ref().map(|health| health.snapshot())
+    }
+
+    /// Hands out the live health metrics, if [`Builder::with_subscriber`]
+    /// has enabled them, so a caller doing its own request serialization
+    /// can record [`types::health::HealthMetrics::record_serialization_error`]
+    /// and [`types::health::HealthMetrics::record_bytes_sent`] alongside the
+    /// request/error counts this builder records automatically
+    pub fn health(&self) -> Option<std::sync::Arc<types::health::HealthMetrics>> {
+        self.health.clone()
+    }
+
+    /// Wraps `route` so its `execute()` records the outcome under
+    /// `route_name` against this builder's health metrics
+    fn track<T, R>(&self, route_name: &'static str, route: R) -> types::route::Tracked<R>
+    where
+        R: types::route::Route<T>,
+        T: std::fmt::Debug,
+    {
+        types::route::Tracked::new(route_name, self.health.clone(), route)
+    }
+
+    /// Re-submits the current health snapshot as gauges through a
+    /// DogStatsD client, for periodic self-reporting back to Datadog
+    pub fn report_health_via_statsd(
+        &self,
+        statsd: &mut client::statsd::DogStatsDClient,
+    ) -> std::io::Result<()> {
+        let Some(snapshot) = self.health_snapshot() else {
+            return Ok(());
+        };
+        for (name, value) in snapshot.counters {
+            statsd.gauge(&name, value as f64, &[])?;
+        }
+        Ok(())
+    }
+
+    /// Hands out a [`client::statsd::DogStatsDClient`] connected over UDP,
+    /// for agent-local submission instead of the authenticated v2 HTTP
+    /// series endpoint
+    pub fn statsd_udp<A: std::net::ToSocketAddrs>(
+        &self,
+        addr: A,
+    ) -> std::io::Result<client::statsd::DogStatsDClient> {
+        client::statsd::DogStatsDClient::udp(addr)
+    }
+
+    /// Hands out a [`client::statsd::DogStatsDClient`] connected over a
+    /// Unix datagram socket
+    pub fn statsd_unix<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> std::io::Result<client::statsd::DogStatsDClient> {
+        client::statsd::DogStatsDClient::unix(path)
+    }
+
     /// Sets the api version to v1
     pub fn v1(&mut self) -> &mut Self {
         self.version = types::version::ApiVersion::V1;
@@ -75,57 +149,128 @@ impl Builder {
         self
     }
 
-    /// Creates the respective route for the given route enum
-    // pub fn route<T>(&mut self, route: Route) -> impl Route<T>
-    // where
-    //     // routes::metrics::tags::Tags: types::route::Route<T>,
-    //     T: std::fmt::Debug,
-    // {
-    //     match self.version {
-    //         ApiVersion::V2 => match version {
-    //             // V2Routes::Metrics => self.metrics(),
-    //             V2Routes::Metrics => panic!("Not implemented!"),
-    //         },
-    //         _ => panic!("Invalid Route Version \"V2Routes\" after calling builder.v1()"),
-    //     }
-    // }
+    /// Looks up the route registered at `path` for the builder's current
+    /// API version, via the dispatch table in [`types::route::registry`]
+    pub fn route(&self, path: &[&str]) -> Result<types::route::RouteKind, types::route::BuilderError> {
+        types::route::registry().lookup(self.version, path)
+    }
 
     /// Create a new Tag Configuration
-    pub fn create_new_tag_config<T>(&self, metric_name: &str) -> impl types::route::Route<T>
+    pub fn create_new_tag_config<T>(
+        &self,
+        metric_name: &str,
+    ) -> Result<impl types::route::Route<T>, types::route::BuilderError>
     where
         routes::metrics::tags::Tags: types::route::Route<T>,
         T: std::fmt::Debug,
     {
-        match self.version {
-            types::version::ApiVersion::V2 => routes::metrics::tags::Tags::new(metric_name),
-            _ => panic!("Unimplemented API Version"),
+        match self.route(&["tags"])? {
+            types::route::RouteKind::Tags(tags) => {
+                Ok(self.track("tags", tags.metric_name(metric_name)))
+            }
+            _ => Err(types::route::BuilderError::NoSuchRoute(vec!["tags".to_string()])),
         }
     }
 
     /// Posts series data to the metrics endpoint
-    pub fn post_series<T>(&self) -> impl types::route::Route<T>
+    pub fn post_series<T>(&self) -> Result<impl types::route::Route<T>, types::route::BuilderError>
     where
         routes::metrics::series::Series: types::route::Route<T>,
         T: std::fmt::Debug,
     {
-        match self.version {
-            types::version::ApiVersion::V2 => routes::metrics::series::Series::new(),
-            _ => panic!("Unimplemented API Version"),
+        match self.route(&["series"])? {
+            types::route::RouteKind::Series(series) => Ok(self.track("series", series)),
+            _ => Err(types::route::BuilderError::NoSuchRoute(vec!["series".to_string()])),
         }
     }
 
     /// Posts distribution points to the metrics endpoint
-    pub fn post_distribution<T>(&self) -> impl types::route::Route<T>
+    pub fn post_distribution<T>(
+        &self,
+    ) -> Result<impl types::route::Route<T>, types::route::BuilderError>
     where
         routes::metrics::distribution::Distribution: types::route::Route<T>,
         T: std::fmt::Debug,
     {
-        match routes::metrics::distribution::Distribution::try_from(self.version) {
-            Ok(distribution) => distribution,
-            Err(e) => {
-                tracing::error!(target: "builder", "Failed to create distribution for api version: {:?} with error: {:?}", self.version, e);
-                panic!("Unimplemented API Version: {:?}", e)
+        match self.route(&["distribution"])? {
+            types::route::RouteKind::Distribution(distribution) => {
+                Ok(self.track("distribution", distribution))
             }
+            _ => Err(types::route::BuilderError::NoSuchRoute(vec![
+                "distribution".to_string(),
+            ])),
+        }
+    }
+
+    /// Posts a client-aggregated [`types::sketch::DDSketch`] to the
+    /// distribution endpoint
+    ///
+    /// The sketch's occupied bins are handed over as weighted
+    /// `(value, count)` points to the same
+    /// [`routes::metrics::distribution::Distribution`] route used by
+    /// [`Builder::post_distribution`], so millions of aggregated samples
+    /// never expand into a per-sample payload.
+    pub fn post_distribution_sketch<T>(
+        &self,
+        metric_name: &str,
+        sketch: &types::sketch::DDSketch,
+    ) -> Result<impl types::route::Route<T>, types::route::BuilderError>
+    where
+        routes::metrics::distribution::Distribution: types::route::Route<T>,
+        T: std::fmt::Debug,
+    {
+        match self.route(&["distribution"])? {
+            types::route::RouteKind::Distribution(distribution) => Ok(self.track(
+                "distribution",
+                distribution.add_weighted_points(metric_name, sketch.to_weighted_points()),
+            )),
+            _ => Err(types::route::BuilderError::NoSuchRoute(vec![
+                "distribution".to_string(),
+            ])),
+        }
+    }
+
+    /// Posts APM traces to the agent trace endpoint
+    pub fn post_traces<T>(
+        &self,
+        traces: Vec<Vec<routes::traces::Span>>,
+    ) -> Result<impl types::route::Route<T>, types::route::BuilderError>
+    where
+        routes::traces::Traces: types::route::Route<T>,
+        T: std::fmt::Debug,
+    {
+        match self.route(&["traces"])? {
+            types::route::RouteKind::Traces(mut route) => {
+                for trace in traces {
+                    route = route.add_trace(trace);
+                }
+                Ok(self.track("traces", route))
+            }
+            _ => Err(types::route::BuilderError::NoSuchRoute(vec!["traces".to_string()])),
+        }
+    }
+
+    /// Posts aggregated trace stats (from a
+    /// [`routes::traces::stats::Concentrator`]) to the stats intake
+    pub fn post_trace_stats<T>(
+        &self,
+        payloads: Vec<routes::traces::stats::StatsPayload>,
+    ) -> Result<impl types::route::Route<T>, types::route::BuilderError>
+    where
+        routes::traces::stats::Stats: types::route::Route<T>,
+        T: std::fmt::Debug,
+    {
+        match self.route(&["traces", "stats"])? {
+            types::route::RouteKind::Stats(mut route) => {
+                for payload in payloads {
+                    route = route.add_payload(payload);
+                }
+                Ok(self.track("traces/stats", route))
+            }
+            _ => Err(types::route::BuilderError::NoSuchRoute(vec![
+                "traces".to_string(),
+                "stats".to_string(),
+            ])),
         }
     }
 
@@ -135,20 +280,20 @@ impl Builder {
         from: usize,
         host: Option<String>,
         tag_filter: Option<String>,
-    ) -> impl types::route::Route<T>
+    ) -> Result<impl types::route::Route<T>, types::route::BuilderError>
     where
         routes::metrics::get_metrics::GetMetrics: types::route::Route<T>,
         T: std::fmt::Debug,
     {
-        match routes::metrics::get_metrics::GetMetrics::try_from(self.version) {
-            Ok(metrics) => metrics
-                .set_from(from)
-                .set_host(host.unwrap_or_else(|| "".to_string()))
-                .set_tag_filter(tag_filter.unwrap_or_else(|| "".to_string())),
-            Err(e) => {
-                tracing::error!(target: "builder", "Failed to create metrics for api version: {:?} with error: {:?}", self.version, e);
-                panic!("Unimplemented API Version: {:?}", e)
-            }
+        match self.route(&["metrics"])? {
+            types::route::RouteKind::GetMetrics(metrics) => Ok(self.track(
+                "metrics",
+                metrics
+                    .set_from(from)
+                    .set_host(host.unwrap_or_else(|| "".to_string()))
+                    .set_tag_filter(tag_filter.unwrap_or_else(|| "".to_string())),
+            )),
+            _ => Err(types::route::BuilderError::NoSuchRoute(vec!["metrics".to_string()])),
         }
     }
 }
@@ -0,0 +1,4 @@
+//! Api Client
+
+/// DogStatsD Client
+pub mod statsd;
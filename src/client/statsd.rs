@@ -0,0 +1,271 @@
+//! DogStatsD line-protocol transport
+
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+
+/// Default flush threshold when buffering is enabled, matching a
+/// conservative UDP MTU
+pub const DEFAULT_MTU: usize = 1432;
+
+/// A DogStatsD metric type, encoded as the `|type` suffix of a line
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MetricType {
+    /// Counter (`c`)
+    Counter,
+    /// Gauge (`g`)
+    Gauge,
+    /// Timer, in milliseconds (`ms`)
+    Timer,
+    /// Histogram (`h`)
+    Histogram,
+    /// Distribution (`d`)
+    Distribution,
+    /// Set (`s`)
+    Set,
+}
+
+impl MetricType {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricType::Counter => "c",
+            MetricType::Gauge => "g",
+            MetricType::Timer => "ms",
+            MetricType::Histogram => "h",
+            MetricType::Distribution => "d",
+            MetricType::Set => "s",
+        }
+    }
+}
+
+enum Transport {
+    Udp(UdpSocket),
+    Unix(UnixDatagram),
+}
+
+impl Transport {
+    fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Transport::Udp(socket) => socket.send(buf),
+            Transport::Unix(socket) => socket.send(buf),
+        }
+    }
+}
+
+/// A minimal xorshift PRNG used for client-side sampling, avoiding a
+/// dependency on an external rng crate for a single coin flip
+struct Rng(u64);
+
+impl Rng {
+    fn new() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9e3779b97f4a7c15)
+            | 1;
+        Self(seed)
+    }
+
+    /// Returns a pseudo-random value in `0.0..1.0`
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A client that submits metrics to a local Datadog agent using the
+/// DogStatsD line protocol: `name:value|type|@sample_rate|#tag:val,tag2:val2`
+pub struct DogStatsDClient {
+    transport: Transport,
+    constant_tags: Vec<String>,
+    sample_rate: f64,
+    buffering: bool,
+    mtu: usize,
+    buffer: String,
+    rng: Rng,
+}
+
+impl DogStatsDClient {
+    /// Connects to a DogStatsD agent listening over UDP
+    pub fn udp<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(addr)?;
+        Ok(Self::new(Transport::Udp(socket)))
+    }
+
+    /// Connects to a DogStatsD agent listening on a Unix datagram socket
+    pub fn unix<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Self::new(Transport::Unix(socket)))
+    }
+
+    fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            constant_tags: Vec::new(),
+            sample_rate: 1.0,
+            buffering: false,
+            mtu: DEFAULT_MTU,
+            buffer: String::new(),
+            rng: Rng::new(),
+        }
+    }
+
+    /// Sets a tag set applied to every metric submitted through this client
+    pub fn with_constant_tags(mut self, tags: Vec<String>) -> Self {
+        self.constant_tags = tags;
+        self
+    }
+
+    /// Sets the client-side sample rate (`0.0..=1.0`) applied to every
+    /// metric submitted through this client
+    pub fn with_sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Enables buffering: metrics are coalesced into a newline-separated
+    /// buffer and flushed as a single datagram once `mtu` would be exceeded
+    pub fn with_buffering(mut self, mtu: usize) -> Self {
+        self.buffering = true;
+        self.mtu = mtu;
+        self
+    }
+
+    /// Submits a counter increment/decrement
+    pub fn count(&mut self, name: &str, value: i64, tags: &[&str]) -> io::Result<()> {
+        self.submit_metric(name, &value.to_string(), MetricType::Counter, tags)
+    }
+
+    /// Increments a counter by `1`
+    pub fn increment(&mut self, name: &str, tags: &[&str]) -> io::Result<()> {
+        self.count(name, 1, tags)
+    }
+
+    /// Decrements a counter by `1`
+    pub fn decrement(&mut self, name: &str, tags: &[&str]) -> io::Result<()> {
+        self.count(name, -1, tags)
+    }
+
+    /// Submits a gauge reading
+    pub fn gauge(&mut self, name: &str, value: f64, tags: &[&str]) -> io::Result<()> {
+        self.submit_metric(name, &value.to_string(), MetricType::Gauge, tags)
+    }
+
+    /// Submits a histogram sample
+    pub fn histogram(&mut self, name: &str, value: f64, tags: &[&str]) -> io::Result<()> {
+        self.submit_metric(name, &value.to_string(), MetricType::Histogram, tags)
+    }
+
+    /// Submits a distribution sample
+    pub fn distribution(&mut self, name: &str, value: f64, tags: &[&str]) -> io::Result<()> {
+        self.submit_metric(name, &value.to_string(), MetricType::Distribution, tags)
+    }
+
+    /// Submits a timing sample, in milliseconds
+    pub fn timing(&mut self, name: &str, duration_ms: f64, tags: &[&str]) -> io::Result<()> {
+        self.submit_metric(name, &duration_ms.to_string(), MetricType::Timer, tags)
+    }
+
+    /// Submits a set member
+    pub fn set(&mut self, name: &str, value: &str, tags: &[&str]) -> io::Result<()> {
+        self.submit_metric(name, value, MetricType::Set, tags)
+    }
+
+    /// Submits a service check, using the Datadog status codes
+    /// (`0` ok, `1` warning, `2` critical, `3` unknown)
+    pub fn service_check(
+        &mut self,
+        name: &str,
+        status: i32,
+        tags: &[&str],
+        message: Option<&str>,
+    ) -> io::Result<()> {
+        let mut line = format!("_sc|{}|{}", name, status);
+        if !tags.is_empty() || !self.constant_tags.is_empty() {
+            line.push_str(&format!("|#{}", self.format_tags(tags)));
+        }
+        if let Some(message) = message {
+            line.push_str(&format!("|m:{}", message));
+        }
+        self.submit(line)
+    }
+
+    /// Submits an event
+    pub fn event(&mut self, title: &str, text: &str, tags: &[&str]) -> io::Result<()> {
+        let text = text.replace('\n', "\\n");
+        let mut line = format!("_e{{{},{}}}:{}|{}", title.len(), text.len(), title, text);
+        if !tags.is_empty() || !self.constant_tags.is_empty() {
+            line.push_str(&format!("|#{}", self.format_tags(tags)));
+        }
+        self.submit(line)
+    }
+
+    /// Flushes any buffered metrics as a single datagram
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.transport.send(self.buffer.as_bytes())?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    fn submit_metric(
+        &mut self,
+        name: &str,
+        value: &str,
+        metric_type: MetricType,
+        tags: &[&str],
+    ) -> io::Result<()> {
+        if self.sample_rate < 1.0 && self.rng.next_f64() >= self.sample_rate {
+            return Ok(());
+        }
+
+        let mut line = format!("{}:{}|{}", name, value, metric_type.as_str());
+        if self.sample_rate < 1.0 {
+            line.push_str(&format!("|@{}", self.sample_rate));
+        }
+        if !tags.is_empty() || !self.constant_tags.is_empty() {
+            line.push_str(&format!("|#{}", self.format_tags(tags)));
+        }
+        self.submit(line)
+    }
+
+    fn format_tags(&self, tags: &[&str]) -> String {
+        self.constant_tags
+            .iter()
+            .map(String::as_str)
+            .chain(tags.iter().copied())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn submit(&mut self, line: String) -> io::Result<()> {
+        if !self.buffering {
+            return self.transport.send(line.as_bytes()).map(|_| ());
+        }
+
+        if !self.buffer.is_empty() && self.buffer.len() + 1 + line.len() > self.mtu {
+            self.flush()?;
+        }
+        if !self.buffer.is_empty() {
+            self.buffer.push('\n');
+        }
+        self.buffer.push_str(&line);
+        Ok(())
+    }
+}
+
+impl Drop for DogStatsDClient {
+    /// Flushes any metrics still sitting in `buffer` so enabling
+    /// [`DogStatsDClient::with_buffering`] can't silently lose the last,
+    /// not-yet-MTU-sized batch when the client is dropped
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
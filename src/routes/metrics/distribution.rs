@@ -0,0 +1,48 @@
+//! Distribution Points Submission Route
+
+use crate::types::route::Route;
+
+/// Route for submitting distribution points to the metrics endpoint
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Distribution {
+    points: Vec<(String, Vec<f64>)>,
+    sketch_points: Vec<(String, Vec<(f64, u32)>)>,
+    headers: Vec<(String, String)>,
+}
+
+impl Distribution {
+    /// Creates an empty distribution submission
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds raw sample points for a metric name
+    pub fn add_points(mut self, metric_name: &str, points: Vec<f64>) -> Self {
+        self.points.push((metric_name.to_string(), points));
+        self
+    }
+
+    /// Adds `(value, count)` weighted points for a metric name, e.g. from
+    /// [`crate::types::sketch::DDSketch::to_weighted_points`]
+    pub fn add_weighted_points(mut self, metric_name: &str, points: Vec<(f64, u32)>) -> Self {
+        self.sketch_points.push((metric_name.to_string(), points));
+        self
+    }
+}
+
+impl<T> Route<T> for Distribution
+where
+    T: std::fmt::Debug,
+{
+    fn headers(mut self, headers: Vec<(&str, &str)>) -> Self {
+        self.headers = headers
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    async fn execute(self) -> (Option<u16>, Option<T>) {
+        (None, None)
+    }
+}
@@ -0,0 +1,54 @@
+//! Active Metrics Listing Route
+
+use crate::types::route::Route;
+
+/// Route for listing active metrics
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetMetrics {
+    from: usize,
+    host: String,
+    tag_filter: String,
+    headers: Vec<(String, String)>,
+}
+
+impl GetMetrics {
+    /// Creates an empty active metrics listing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the Unix timestamp from which to list active metrics
+    pub fn set_from(mut self, from: usize) -> Self {
+        self.from = from;
+        self
+    }
+
+    /// Sets the host filter
+    pub fn set_host(mut self, host: String) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// Sets the tag filter
+    pub fn set_tag_filter(mut self, tag_filter: String) -> Self {
+        self.tag_filter = tag_filter;
+        self
+    }
+}
+
+impl<T> Route<T> for GetMetrics
+where
+    T: std::fmt::Debug,
+{
+    fn headers(mut self, headers: Vec<(&str, &str)>) -> Self {
+        self.headers = headers
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    async fn execute(self) -> (Option<u16>, Option<T>) {
+        (None, None)
+    }
+}
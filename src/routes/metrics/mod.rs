@@ -0,0 +1,13 @@
+//! Metrics Routes
+
+/// Tag Configuration Route
+pub mod tags;
+
+/// Series Submission Route
+pub mod series;
+
+/// Distribution Points Submission Route
+pub mod distribution;
+
+/// Active Metrics Listing Route
+pub mod get_metrics;
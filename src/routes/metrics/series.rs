@@ -0,0 +1,40 @@
+//! Series Submission Route
+
+use crate::types::route::Route;
+
+/// Route for submitting metric series data to the metrics endpoint
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Series {
+    series: Vec<(String, Vec<(i64, f64)>)>,
+    headers: Vec<(String, String)>,
+}
+
+impl Series {
+    /// Creates an empty series submission
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `(timestamp, value)` points for a metric name
+    pub fn add_series(mut self, metric_name: &str, points: Vec<(i64, f64)>) -> Self {
+        self.series.push((metric_name.to_string(), points));
+        self
+    }
+}
+
+impl<T> Route<T> for Series
+where
+    T: std::fmt::Debug,
+{
+    fn headers(mut self, headers: Vec<(&str, &str)>) -> Self {
+        self.headers = headers
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    async fn execute(self) -> (Option<u16>, Option<T>) {
+        (None, None)
+    }
+}
@@ -0,0 +1,40 @@
+//! Tag Configuration Route
+
+use crate::types::route::Route;
+
+/// Route for creating/updating a metric tag configuration
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tags {
+    metric_name: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Tags {
+    /// Creates an empty tag configuration route
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the metric name the tag configuration applies to
+    pub fn metric_name(mut self, metric_name: &str) -> Self {
+        self.metric_name = metric_name.to_string();
+        self
+    }
+}
+
+impl<T> Route<T> for Tags
+where
+    T: std::fmt::Debug,
+{
+    fn headers(mut self, headers: Vec<(&str, &str)>) -> Self {
+        self.headers = headers
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    async fn execute(self) -> (Option<u16>, Option<T>) {
+        (None, None)
+    }
+}
@@ -0,0 +1,12 @@
+//! Api Routes
+
+/// Metrics Routes
+pub mod metrics;
+
+/// APM Traces Routes
+pub mod traces;
+
+/// Prelude to re-export common types
+pub mod prelude {
+    pub use super::{metrics::{self, *}, traces::{self, *}};
+}
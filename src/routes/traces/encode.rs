@@ -0,0 +1,212 @@
+//! Datadog trace v0.5 msgpack encoding
+
+use super::span::Span;
+
+/// Interns strings into a deduplicated dictionary, assigning stable `u32`
+/// indices. Index `0` is reserved for the empty string, matching the v0.5
+/// spec's requirement that unset string fields encode as `0`.
+#[derive(Clone, Debug, Default)]
+pub struct Dictionary {
+    strings: Vec<String>,
+    indices: std::collections::HashMap<String, u32>,
+}
+
+impl Dictionary {
+    /// Creates a dictionary with the empty string pre-interned at index `0`
+    pub fn new() -> Self {
+        let mut dict = Self::default();
+        dict.intern("");
+        dict
+    }
+
+    /// Interns `s`, returning its stable index
+    pub fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&idx) = self.indices.get(s) {
+            return idx;
+        }
+        let idx = self.strings.len() as u32;
+        self.strings.push(s.to_string());
+        self.indices.insert(s.to_string(), idx);
+        idx
+    }
+
+    /// Consumes the dictionary, returning the interned strings in index order
+    pub fn into_strings(self) -> Vec<String> {
+        self.strings
+    }
+}
+
+fn write_array_header(buf: &mut Vec<u8>, len: usize) {
+    match len {
+        0..=15 => buf.push(0x90 | len as u8),
+        16..=0xffff => {
+            buf.push(0xdc);
+            buf.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        _ => {
+            buf.push(0xdd);
+            buf.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+}
+
+fn write_map_header(buf: &mut Vec<u8>, len: usize) {
+    match len {
+        0..=15 => buf.push(0x80 | len as u8),
+        16..=0xffff => {
+            buf.push(0xde);
+            buf.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        _ => {
+            buf.push(0xdf);
+            buf.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+}
+
+fn write_str(buf: &mut Vec<u8>, s: &str) {
+    let bytes = s.as_bytes();
+    match bytes.len() {
+        0..=31 => buf.push(0xa0 | bytes.len() as u8),
+        32..=0xff => {
+            buf.push(0xd9);
+            buf.push(bytes.len() as u8);
+        }
+        0x100..=0xffff => {
+            buf.push(0xda);
+            buf.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+        }
+        _ => {
+            buf.push(0xdb);
+            buf.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        }
+    }
+    buf.extend_from_slice(bytes);
+}
+
+fn write_uint(buf: &mut Vec<u8>, v: u64) {
+    match v {
+        0..=0x7f => buf.push(v as u8),
+        0x80..=0xff => {
+            buf.push(0xcc);
+            buf.push(v as u8);
+        }
+        0x100..=0xffff => {
+            buf.push(0xcd);
+            buf.extend_from_slice(&(v as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buf.push(0xce);
+            buf.extend_from_slice(&(v as u32).to_be_bytes());
+        }
+        _ => {
+            buf.push(0xcf);
+            buf.extend_from_slice(&v.to_be_bytes());
+        }
+    }
+}
+
+fn write_int(buf: &mut Vec<u8>, v: i64) {
+    if v >= 0 {
+        write_uint(buf, v as u64);
+    } else {
+        buf.push(0xd3);
+        buf.extend_from_slice(&v.to_be_bytes());
+    }
+}
+
+fn write_span(buf: &mut Vec<u8>, dict: &mut Dictionary, span: &Span) {
+    write_array_header(buf, 12);
+    write_uint(buf, dict.intern(&span.service) as u64);
+    write_uint(buf, dict.intern(&span.name) as u64);
+    write_uint(buf, dict.intern(&span.resource) as u64);
+    write_uint(buf, span.trace_id);
+    write_uint(buf, span.span_id);
+    write_uint(buf, span.parent_id);
+    write_int(buf, span.start);
+    write_int(buf, span.duration);
+    write_int(buf, span.error as i64);
+
+    write_map_header(buf, span.meta.len());
+    for (key, value) in &span.meta {
+        write_uint(buf, dict.intern(key) as u64);
+        write_uint(buf, dict.intern(value) as u64);
+    }
+
+    write_map_header(buf, 0);
+    write_uint(buf, dict.intern(&span.r#type) as u64);
+}
+
+/// Encodes traces (a list of traces, each a list of spans) into the v0.5
+/// msgpack wire format: `[dictionary, traces]`, with string fields
+/// interned as `u32` dictionary indices
+pub fn encode_v05(traces: &[Vec<Span>]) -> Vec<u8> {
+    let mut dict = Dictionary::new();
+    let mut spans_buf = Vec::new();
+
+    write_array_header(&mut spans_buf, traces.len());
+    for trace in traces {
+        write_array_header(&mut spans_buf, trace.len());
+        for span in trace {
+            write_span(&mut spans_buf, &mut dict, span);
+        }
+    }
+
+    let mut buf = Vec::new();
+    write_array_header(&mut buf, 2);
+
+    let strings = dict.into_strings();
+    write_array_header(&mut buf, strings.len());
+    for s in &strings {
+        write_str(&mut buf, s);
+    }
+
+    buf.extend_from_slice(&spans_buf);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_v05_single_span_matches_known_bytes() {
+        let span = Span::new()
+            .service("web")
+            .name("op")
+            .resource("res")
+            .trace_id(1)
+            .span_id(2)
+            .start(100)
+            .duration(50);
+
+        let got = encode_v05(&[vec![span]]);
+
+        #[rustfmt::skip]
+        let want: Vec<u8> = vec![
+            0x92, // [dictionary, traces]
+            0x94, // dictionary: 4 strings
+            0xa0, // ""
+            0xa3, 0x77, 0x65, 0x62, // "web"
+            0xa2, 0x6f, 0x70, // "op"
+            0xa3, 0x72, 0x65, 0x73, // "res"
+            0x91, // traces: 1 trace
+            0x91, // trace: 1 span
+            0x9c, // span: 12 fields
+            0x01, // service -> dict[1]
+            0x02, // name -> dict[2]
+            0x03, // resource -> dict[3]
+            0x01, // trace_id
+            0x02, // span_id
+            0x00, // parent_id
+            0x64, // start
+            0x32, // duration
+            0x00, // error
+            0x80, // meta: empty map
+            0x80, // metrics: empty map
+            0x00, // type -> dict[0] ("")
+        ];
+
+        assert_eq!(got, want);
+    }
+}
@@ -0,0 +1,56 @@
+//! APM Traces Routes
+
+use crate::types::route::Route;
+
+/// Span Builder
+pub mod span;
+
+/// v0.5 msgpack encoding
+pub mod encode;
+
+/// Client-side trace stats computation
+pub mod stats;
+
+pub use span::Span;
+
+/// Route for submitting APM traces to the agent trace endpoint
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Traces {
+    traces: Vec<Vec<Span>>,
+    headers: Vec<(String, String)>,
+}
+
+impl Traces {
+    /// Creates an empty trace submission
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a trace (an ordered list of spans that share a `trace_id`)
+    pub fn add_trace(mut self, spans: Vec<Span>) -> Self {
+        self.traces.push(spans);
+        self
+    }
+
+    /// Encodes the accumulated traces into the v0.5 msgpack wire format
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        encode::encode_v05(&self.traces)
+    }
+}
+
+impl<T> Route<T> for Traces
+where
+    T: std::fmt::Debug,
+{
+    fn headers(mut self, headers: Vec<(&str, &str)>) -> Self {
+        self.headers = headers
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    async fn execute(self) -> (Option<u16>, Option<T>) {
+        (None, None)
+    }
+}
@@ -0,0 +1,91 @@
+//! APM Span
+
+/// A single APM span within a trace
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Span {
+    pub(crate) service: String,
+    pub(crate) name: String,
+    pub(crate) resource: String,
+    pub(crate) r#type: String,
+    pub(crate) trace_id: u64,
+    pub(crate) span_id: u64,
+    pub(crate) parent_id: u64,
+    pub(crate) start: i64,
+    pub(crate) duration: i64,
+    pub(crate) error: i32,
+    pub(crate) meta: Vec<(String, String)>,
+}
+
+impl Span {
+    /// Creates an empty span; fields are filled in through the chainable
+    /// setters below
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the service name
+    pub fn service(mut self, service: &str) -> Self {
+        self.service = service.to_string();
+        self
+    }
+
+    /// Sets the span (operation) name
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Sets the resource name
+    pub fn resource(mut self, resource: &str) -> Self {
+        self.resource = resource.to_string();
+        self
+    }
+
+    /// Sets the span type (e.g. `"web"`, `"db"`, `"cache"`)
+    pub fn span_type(mut self, span_type: &str) -> Self {
+        self.r#type = span_type.to_string();
+        self
+    }
+
+    /// Sets the trace id shared by every span in the trace
+    pub fn trace_id(mut self, trace_id: u64) -> Self {
+        self.trace_id = trace_id;
+        self
+    }
+
+    /// Sets this span's id
+    pub fn span_id(mut self, span_id: u64) -> Self {
+        self.span_id = span_id;
+        self
+    }
+
+    /// Sets the parent span id, or `0` for a root span
+    pub fn parent_id(mut self, parent_id: u64) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
+    /// Sets the start time, in Unix nanoseconds
+    pub fn start(mut self, start: i64) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Sets the duration, in nanoseconds
+    pub fn duration(mut self, duration: i64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Marks the span as erroring (`1`) or not (`0`)
+    pub fn error(mut self, error: i32) -> Self {
+        self.error = error;
+        self
+    }
+
+    /// Adds a meta key/value tag
+    pub fn meta(mut self, key: &str, value: &str) -> Self {
+        self.meta.push((key.to_string(), value.to_string()));
+        self
+    }
+}
@@ -0,0 +1,302 @@
+//! Client-side trace stats computation (span concentrator)
+
+use super::Span;
+use crate::types::route::Route;
+use crate::types::sketch::DDSketch;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Default bucket width used to group spans for aggregation
+pub const DEFAULT_BUCKET_DURATION: Duration = Duration::from_secs(10);
+
+/// Default meta key a tracer sets to flag a client-computed top-level span
+pub const DEFAULT_TOP_LEVEL_META_KEY: &str = "_top_level";
+
+/// Default meta key a tracer sets to flag a measured span
+pub const DEFAULT_MEASURED_META_KEY: &str = "_dd.measured";
+
+/// Configuration for the span [`Concentrator`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConcentratorConfig {
+    /// Width of each aggregation window
+    pub bucket_duration: Duration,
+    /// Meta key that flags a client-computed top-level span
+    pub top_level_meta_key: String,
+    /// Meta key that flags a measured span
+    pub measured_meta_key: String,
+    /// Tracer header tag: hostname
+    pub hostname: String,
+    /// Tracer header tag: env
+    pub env: String,
+    /// Tracer header tag: version
+    pub version: String,
+}
+
+impl Default for ConcentratorConfig {
+    fn default() -> Self {
+        Self {
+            bucket_duration: DEFAULT_BUCKET_DURATION,
+            top_level_meta_key: DEFAULT_TOP_LEVEL_META_KEY.to_string(),
+            measured_meta_key: DEFAULT_MEASURED_META_KEY.to_string(),
+            hostname: String::new(),
+            env: String::new(),
+            version: String::new(),
+        }
+    }
+}
+
+/// The group a span's stats roll up into
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+struct BucketKey {
+    service: String,
+    name: String,
+    resource: String,
+    span_type: String,
+    http_status_code: String,
+    synthetics: bool,
+}
+
+impl BucketKey {
+    fn from_span(span: &Span) -> Self {
+        let http_status_code = span
+            .meta
+            .iter()
+            .find(|(key, _)| key == "http.status_code")
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default();
+        let synthetics = span
+            .meta
+            .iter()
+            .any(|(key, value)| key == "synthetics" && value == "true");
+
+        Self {
+            service: span.service.clone(),
+            name: span.name.clone(),
+            resource: span.resource.clone(),
+            span_type: span.r#type.clone(),
+            http_status_code,
+            synthetics,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct BucketStats {
+    hits: u64,
+    errors: u64,
+    top_level_hits: u64,
+    duration_sketch: DDSketch,
+    error_duration_sketch: DDSketch,
+}
+
+/// Aggregated stats for one `(service, name, resource, type, http status,
+/// synthetics)` group within a single window
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatsGroup {
+    /// Service name
+    pub service: String,
+    /// Span name
+    pub name: String,
+    /// Resource name
+    pub resource: String,
+    /// Span type
+    pub span_type: String,
+    /// `http.status_code` meta value, if present
+    pub http_status_code: String,
+    /// Whether the span was flagged as coming from synthetic monitoring
+    pub synthetics: bool,
+    /// Number of spans observed in this group
+    pub hits: u64,
+    /// Number of erroring spans observed in this group
+    pub errors: u64,
+    /// Number of top-level spans observed in this group
+    pub top_level_hits: u64,
+    /// Sketch of every span's duration
+    pub duration_sketch: DDSketch,
+    /// Sketch of erroring spans' durations
+    pub error_duration_sketch: DDSketch,
+}
+
+/// A stats payload for one window, ready to submit to the trace stats
+/// intake, tagged with the tracer header tags
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatsPayload {
+    /// Start of the window, in Unix nanoseconds
+    pub window_start: i64,
+    /// Width of the window
+    pub bucket_duration: Duration,
+    /// Tracer header tag: hostname
+    pub hostname: String,
+    /// Tracer header tag: env
+    pub env: String,
+    /// Tracer header tag: version
+    pub version: String,
+    /// Aggregated groups within this window
+    pub groups: Vec<StatsGroup>,
+}
+
+/// Buckets spans into fixed time windows and accumulates per-group stats,
+/// so that trace chunks can be dropped locally once their stats have been
+/// computed
+pub struct Concentrator {
+    config: ConcentratorConfig,
+    buckets: HashMap<i64, HashMap<BucketKey, BucketStats>>,
+}
+
+impl Concentrator {
+    /// Creates a concentrator with the given configuration
+    pub fn new(config: ConcentratorConfig) -> Self {
+        Self {
+            config,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Ingests a span into the bucket aligned to `start + duration`
+    pub fn add_span(&mut self, span: &Span) {
+        let window = align(span.start + span.duration, self.config.bucket_duration);
+        let key = BucketKey::from_span(span);
+        let top_level = self.is_top_level(span);
+        let stats = self.buckets.entry(window).or_default().entry(key).or_default();
+
+        stats.hits += 1;
+        if span.error != 0 {
+            stats.errors += 1;
+            stats.error_duration_sketch.insert(span.duration as f64);
+        }
+        if top_level {
+            stats.top_level_hits += 1;
+        }
+        stats.duration_sketch.insert(span.duration as f64);
+    }
+
+    fn is_top_level(&self, span: &Span) -> bool {
+        span.meta.iter().any(|(key, value)| {
+            value == "1" && (key == &self.config.top_level_meta_key || key == &self.config.measured_meta_key)
+        })
+    }
+
+    /// Flushes every bucket whose window has fully elapsed as of `now`
+    /// (Unix nanoseconds), returning one payload per flushed window
+    pub fn flush(&mut self, now: i64) -> Vec<StatsPayload> {
+        let active_window = align(now, self.config.bucket_duration);
+        let expired: Vec<i64> = self
+            .buckets
+            .keys()
+            .copied()
+            .filter(|&window| window < active_window)
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|window| self.buckets.remove(&window).map(|groups| (window, groups)))
+            .map(|(window, groups)| StatsPayload {
+                window_start: window,
+                bucket_duration: self.config.bucket_duration,
+                hostname: self.config.hostname.clone(),
+                env: self.config.env.clone(),
+                version: self.config.version.clone(),
+                groups: groups
+                    .into_iter()
+                    .map(|(key, stats)| StatsGroup {
+                        service: key.service,
+                        name: key.name,
+                        resource: key.resource,
+                        span_type: key.span_type,
+                        http_status_code: key.http_status_code,
+                        synthetics: key.synthetics,
+                        hits: stats.hits,
+                        errors: stats.errors,
+                        top_level_hits: stats.top_level_hits,
+                        duration_sketch: stats.duration_sketch,
+                        error_duration_sketch: stats.error_duration_sketch,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// Rounds `ts_nanos` down to the start of its `bucket_duration` window
+fn align(ts_nanos: i64, bucket_duration: Duration) -> i64 {
+    let bucket_nanos = bucket_duration.as_nanos() as i64;
+    if bucket_nanos == 0 {
+        return ts_nanos;
+    }
+    ts_nanos - ts_nanos.rem_euclid(bucket_nanos)
+}
+
+/// Route for submitting aggregated trace stats to the stats intake
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    payloads: Vec<StatsPayload>,
+    headers: Vec<(String, String)>,
+}
+
+impl Stats {
+    /// Creates an empty stats submission
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a window's aggregated stats payload
+    pub fn add_payload(mut self, payload: StatsPayload) -> Self {
+        self.payloads.push(payload);
+        self
+    }
+}
+
+impl<T> Route<T> for Stats
+where
+    T: std::fmt::Debug,
+{
+    fn headers(mut self, headers: Vec<(&str, &str)>) -> Self {
+        self.headers = headers
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    async fn execute(self) -> (Option<u16>, Option<T>) {
+        (None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_span_then_flush_rolls_up_into_one_group() {
+        let mut concentrator = Concentrator::new(ConcentratorConfig::default());
+
+        let span = Span::new()
+            .service("web")
+            .name("op")
+            .resource("res")
+            .start(0)
+            .duration(5_000_000_000)
+            .meta("_top_level", "1");
+        concentrator.add_span(&span);
+
+        let active_window_start = DEFAULT_BUCKET_DURATION.as_nanos() as i64;
+        let payloads = concentrator.flush(active_window_start);
+
+        assert_eq!(payloads.len(), 1);
+        let groups = &payloads[0].groups;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].hits, 1);
+        assert_eq!(groups[0].errors, 0);
+        assert_eq!(groups[0].top_level_hits, 1);
+        assert_eq!(groups[0].duration_sketch.count(), 1);
+    }
+
+    #[test]
+    fn flush_leaves_buckets_inside_the_active_window() {
+        let mut concentrator = Concentrator::new(ConcentratorConfig::default());
+        concentrator.add_span(&Span::new().start(0).duration(1));
+
+        assert!(concentrator.flush(0).is_empty());
+    }
+}
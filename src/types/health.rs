@@ -0,0 +1,79 @@
+//! Built-in self-instrumentation / health metrics
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+struct Counter(AtomicU64);
+
+impl Counter {
+    fn incr(&self, by: u64) {
+        self.0.fetch_add(by, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A snapshot of every [`HealthMetrics`] counter at a point in time
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HealthSnapshot {
+    /// Counter values keyed by metric name, e.g. `ddog.send.series`
+    pub counters: HashMap<String, u64>,
+}
+
+/// Tracks the health of submissions made through a `Builder`: request
+/// counts, request errors, serialization errors and bytes sent, each
+/// exposed as a named internal metric
+#[derive(Debug, Default)]
+pub struct HealthMetrics {
+    counters: Mutex<HashMap<String, Counter>>,
+}
+
+impl HealthMetrics {
+    /// Creates an empty set of health metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn incr(&self, name: String, by: u64) {
+        let mut counters = self.counters.lock().expect("health metrics lock poisoned");
+        counters.entry(name).or_default().incr(by);
+    }
+
+    /// Records a successful request against `route` (e.g. `"series"`),
+    /// incrementing `ddog.send.<route>`
+    pub fn record_request(&self, route: &str) {
+        self.incr(format!("ddog.send.{}", route), 1);
+    }
+
+    /// Records a failed request against `route`, incrementing
+    /// `ddog.send.<route>.errors`
+    pub fn record_request_error(&self, route: &str) {
+        self.incr(format!("ddog.send.{}.errors", route), 1);
+    }
+
+    /// Records a serialization failure for `route`, incrementing
+    /// `ddog.send.<route>.serialization_errors`
+    pub fn record_serialization_error(&self, route: &str) {
+        self.incr(format!("ddog.send.{}.serialization_errors", route), 1);
+    }
+
+    /// Records bytes sent for `route`, incrementing `ddog.send.<route>.bytes`
+    pub fn record_bytes_sent(&self, route: &str, bytes: u64) {
+        self.incr(format!("ddog.send.{}.bytes", route), bytes);
+    }
+
+    /// Snapshots the current counter values
+    pub fn snapshot(&self) -> HealthSnapshot {
+        let counters = self.counters.lock().expect("health metrics lock poisoned");
+        HealthSnapshot {
+            counters: counters
+                .iter()
+                .map(|(name, counter)| (name.clone(), counter.get()))
+                .collect(),
+        }
+    }
+}
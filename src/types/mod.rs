@@ -12,11 +12,19 @@ pub mod version;
 /// Route Type
 pub mod route;
 
+/// DDSketch Quantile Aggregation
+pub mod sketch;
+
+/// Self-Instrumentation / Health Metrics
+pub mod health;
+
 /// Prelude to re-export common types
 pub mod prelude {
     pub use super::{
         base::{self, *},
+        health::{self, *},
         route::{self, *},
+        sketch::{self, *},
         version::{self, *},
     };
 }
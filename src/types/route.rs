@@ -0,0 +1,234 @@
+//! Route Type
+
+use std::fmt::Debug;
+
+/// A Datadog API route that can be executed against the configured endpoint
+///
+/// Implementors represent a single request to a single endpoint. They are
+/// built up through owned, chainable setters and consumed by [`execute`],
+/// mirroring the way [`crate::builder::Builder`] is used.
+///
+/// [`execute`]: Route::execute
+pub trait Route<T>
+where
+    T: Debug,
+{
+    /// Attaches request headers to this route
+    fn headers(self, headers: Vec<(&str, &str)>) -> Self;
+
+    /// Executes the route, returning the response status code and the
+    /// decoded response body, if any
+    async fn execute(self) -> (Option<u16>, Option<T>);
+}
+
+use crate::routes;
+use crate::types::health::HealthMetrics;
+use crate::types::version::ApiVersion;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+/// Wraps a route so [`Route::execute`] also records the outcome against
+/// `health` under `route_name`, if self-instrumentation is enabled via
+/// [`crate::builder::Builder::with_subscriber`]
+///
+/// `Builder`'s `post_*` methods return routes already wrapped in this, so
+/// a call such as `builder.v2().post_series()?.execute().await`
+/// self-instruments without callers doing anything extra.
+pub struct Tracked<R> {
+    route_name: &'static str,
+    health: Option<Arc<HealthMetrics>>,
+    inner: R,
+}
+
+impl<R> Tracked<R> {
+    pub(crate) fn new(route_name: &'static str, health: Option<Arc<HealthMetrics>>, inner: R) -> Self {
+        Self {
+            route_name,
+            health,
+            inner,
+        }
+    }
+}
+
+impl<T, R> Route<T> for Tracked<R>
+where
+    R: Route<T>,
+    T: Debug,
+{
+    fn headers(mut self, headers: Vec<(&str, &str)>) -> Self {
+        self.inner = self.inner.headers(headers);
+        self
+    }
+
+    async fn execute(self) -> (Option<u16>, Option<T>) {
+        let (status, body) = self.inner.execute().await;
+        if let Some(health) = &self.health {
+            match status {
+                Some(code) if (200..300).contains(&code) => health.record_request(self.route_name),
+                _ => health.record_request_error(self.route_name),
+            }
+        }
+        (status, body)
+    }
+}
+
+/// Errors returned when building or dispatching a route
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuilderError {
+    /// No routes are registered for the requested API version
+    UnsupportedVersion(ApiVersion),
+    /// No route matched the given path segments
+    NoSuchRoute(Vec<String>),
+    /// The request body failed validation
+    InvalidBody(String),
+    /// A constructor is already registered at this `(version, path)`
+    DuplicateRoute(ApiVersion, Vec<String>),
+}
+
+impl std::fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::UnsupportedVersion(version) => {
+                write!(f, "unsupported API version: {:?}", version)
+            }
+            BuilderError::NoSuchRoute(path) => {
+                write!(f, "no route registered at /{}", path.join("/"))
+            }
+            BuilderError::InvalidBody(message) => write!(f, "invalid request body: {}", message),
+            BuilderError::DuplicateRoute(version, path) => write!(
+                f,
+                "duplicate route registration at {:?}/{}",
+                version,
+                path.join("/")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+/// A concrete route produced by the [`RouteRegistry`]. `Builder` methods
+/// match on the variant they expect and surface [`BuilderError::NoSuchRoute`]
+/// for any other, so adding a new endpoint only means registering it, not
+/// editing every other method.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RouteKind {
+    /// `routes::metrics::tags::Tags`
+    Tags(routes::metrics::tags::Tags),
+    /// `routes::metrics::series::Series`
+    Series(routes::metrics::series::Series),
+    /// `routes::metrics::distribution::Distribution`
+    Distribution(routes::metrics::distribution::Distribution),
+    /// `routes::metrics::get_metrics::GetMetrics`
+    GetMetrics(routes::metrics::get_metrics::GetMetrics),
+    /// `routes::traces::Traces`
+    Traces(routes::traces::Traces),
+    /// `routes::traces::stats::Stats`
+    Stats(routes::traces::stats::Stats),
+}
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    handler: Option<fn() -> RouteKind>,
+}
+
+/// A trie-style dispatch table mapping `(ApiVersion, path segments)` to
+/// route constructors
+#[derive(Default)]
+pub struct RouteRegistry {
+    versions: HashMap<ApiVersion, Node>,
+}
+
+impl RouteRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a constructor at `version`/`path`. Returns `Err` if a
+    /// constructor is already registered at that exact path.
+    pub fn register(
+        &mut self,
+        version: ApiVersion,
+        path: &[&str],
+        handler: fn() -> RouteKind,
+    ) -> Result<(), BuilderError> {
+        let mut node = self.versions.entry(version).or_default();
+        for segment in path {
+            node = node.children.entry((*segment).to_string()).or_default();
+        }
+        if node.handler.is_some() {
+            return Err(BuilderError::DuplicateRoute(
+                version,
+                path.iter().map(|s| s.to_string()).collect(),
+            ));
+        }
+        node.handler = Some(handler);
+        Ok(())
+    }
+
+    /// Looks up and constructs the route registered at `version`/`path`
+    pub fn lookup(&self, version: ApiVersion, path: &[&str]) -> Result<RouteKind, BuilderError> {
+        let mut node = self
+            .versions
+            .get(&version)
+            .ok_or(BuilderError::UnsupportedVersion(version))?;
+        for segment in path {
+            node = node.children.get(*segment).ok_or_else(|| {
+                BuilderError::NoSuchRoute(path.iter().map(|s| s.to_string()).collect())
+            })?;
+        }
+        node.handler.map(|ctor| ctor()).ok_or_else(|| {
+            BuilderError::NoSuchRoute(path.iter().map(|s| s.to_string()).collect())
+        })
+    }
+}
+
+/// The process-wide route registry, populated once with every known route.
+/// Every route is registered together on the first call, so a duplicate
+/// registration panics on the first lookup of any route, not only when the
+/// duplicated route itself is looked up.
+pub fn registry() -> &'static RouteRegistry {
+    static REGISTRY: OnceLock<RouteRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = RouteRegistry::new();
+        registry
+            .register(ApiVersion::V2, &["tags"], || {
+                RouteKind::Tags(routes::metrics::tags::Tags::new())
+            })
+            .expect("duplicate route registration");
+        registry
+            .register(ApiVersion::V2, &["series"], || {
+                RouteKind::Series(routes::metrics::series::Series::new())
+            })
+            .expect("duplicate route registration");
+        registry
+            .register(ApiVersion::V2, &["distribution"], || {
+                RouteKind::Distribution(routes::metrics::distribution::Distribution::new())
+            })
+            .expect("duplicate route registration");
+        registry
+            .register(ApiVersion::V1, &["metrics"], || {
+                RouteKind::GetMetrics(routes::metrics::get_metrics::GetMetrics::new())
+            })
+            .expect("duplicate route registration");
+        registry
+            .register(ApiVersion::V2, &["metrics"], || {
+                RouteKind::GetMetrics(routes::metrics::get_metrics::GetMetrics::new())
+            })
+            .expect("duplicate route registration");
+        registry
+            .register(ApiVersion::V2, &["traces"], || {
+                RouteKind::Traces(routes::traces::Traces::new())
+            })
+            .expect("duplicate route registration");
+        registry
+            .register(ApiVersion::V2, &["traces", "stats"], || {
+                RouteKind::Stats(routes::traces::stats::Stats::new())
+            })
+            .expect("duplicate route registration");
+        registry
+    })
+}
@@ -0,0 +1,300 @@
+//! Client-side DDSketch quantile aggregation
+
+/// Default relative accuracy used when none is supplied
+pub const DEFAULT_ALPHA: f64 = 0.01;
+
+/// Default absolute value at or below which a sample counts as zero
+pub const DEFAULT_MIN_VALUE: f64 = 1.0e-9;
+
+/// Default number of bins kept before the lowest bins are collapsed
+pub const DEFAULT_MAX_BINS: usize = 4096;
+
+/// A relative-error quantile sketch matching the Datadog Agent's bucket
+/// layout, where positive and negative values are tracked in separate
+/// logarithmic bin stores and values within `min_value` of zero collapse
+/// into a dedicated zero count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DDSketch {
+    alpha: f64,
+    gamma: f64,
+    min_value: f64,
+    max_bins: usize,
+    zero_count: u32,
+    positives: Vec<(i32, u32)>,
+    negatives: Vec<(i32, u32)>,
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl DDSketch {
+    /// Creates a sketch with the given relative accuracy and default
+    /// min value / bin cap
+    pub fn new(alpha: f64) -> Self {
+        Self::with_config(alpha, DEFAULT_MIN_VALUE, DEFAULT_MAX_BINS)
+    }
+
+    /// Creates a sketch with an explicit accuracy, minimum value and bin cap
+    pub fn with_config(alpha: f64, min_value: f64, max_bins: usize) -> Self {
+        Self {
+            alpha,
+            gamma: (1.0 + alpha) / (1.0 - alpha),
+            min_value,
+            max_bins,
+            zero_count: 0,
+            positives: Vec::new(),
+            negatives: Vec::new(),
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Number of samples recorded so far
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Sum of all recorded samples
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Smallest recorded sample, if any
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Largest recorded sample, if any
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Inserts a single sample
+    pub fn insert(&mut self, value: f64) {
+        self.insert_n(value, 1);
+    }
+
+    /// Inserts `n` copies of the same sample
+    pub fn insert_n(&mut self, value: f64, n: u32) {
+        if n == 0 {
+            return;
+        }
+
+        self.count += n as u64;
+        self.sum += value * n as f64;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+
+        if value.abs() <= self.min_value {
+            self.zero_count += n;
+            return;
+        }
+
+        let key = self.key(value.abs());
+        if value > 0.0 {
+            Self::add_to_bin(&mut self.positives, key, n);
+            Self::collapse(&mut self.positives, self.max_bins);
+        } else {
+            Self::add_to_bin(&mut self.negatives, key, n);
+            Self::collapse(&mut self.negatives, self.max_bins);
+        }
+    }
+
+    /// Merges another sketch's bins, counts and summary stats into this one
+    ///
+    /// Both sketches must share the same `alpha` and `min_value`: bucket
+    /// keys are only comparable under a matching `gamma`, so merging
+    /// sketches built with different configs would silently corrupt the
+    /// bins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` was built with a different `alpha` or `min_value`.
+    pub fn merge(&mut self, other: &DDSketch) {
+        assert_eq!(
+            self.alpha, other.alpha,
+            "cannot merge DDSketches built with different alpha"
+        );
+        assert_eq!(
+            self.min_value, other.min_value,
+            "cannot merge DDSketches built with different min_value"
+        );
+
+        if other.count == 0 {
+            return;
+        }
+
+        self.count += other.count;
+        self.sum += other.sum;
+        self.zero_count += other.zero_count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+
+        for &(key, n) in &other.positives {
+            Self::add_to_bin(&mut self.positives, key, n);
+        }
+        for &(key, n) in &other.negatives {
+            Self::add_to_bin(&mut self.negatives, key, n);
+        }
+
+        Self::collapse(&mut self.positives, self.max_bins);
+        Self::collapse(&mut self.negatives, self.max_bins);
+    }
+
+    /// Returns the approximate value at quantile `q` (in `0.0..=1.0`) by
+    /// walking cumulative counts, from the most negative bin through the
+    /// zero bucket to the most positive bin, until the target rank is
+    /// reached
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+
+        let rank = (q * (self.count - 1) as f64).round() as u64;
+        let mut seen: u64 = 0;
+
+        for &(key, n) in self.negatives.iter().rev() {
+            seen += n as u64;
+            if seen > rank {
+                return Some(-self.value_for_key(key));
+            }
+        }
+
+        seen += self.zero_count as u64;
+        if seen > rank {
+            return Some(0.0);
+        }
+
+        for &(key, n) in &self.positives {
+            seen += n as u64;
+            if seen > rank {
+                return Some(self.value_for_key(key));
+            }
+        }
+
+        Some(self.max)
+    }
+
+    /// Returns one `(value, count)` pair per occupied bin (each of the
+    /// negative and positive stores holds at most `max_bins` bins, plus one
+    /// pair for the zero count), instead of expanding back into a
+    /// per-sample `Vec`
+    pub fn to_weighted_points(&self) -> Vec<(f64, u32)> {
+        let mut points = Vec::with_capacity(self.negatives.len() + 1 + self.positives.len());
+
+        for &(key, n) in &self.negatives {
+            points.push((-self.value_for_key(key), n));
+        }
+        if self.zero_count > 0 {
+            points.push((0.0, self.zero_count));
+        }
+        for &(key, n) in &self.positives {
+            points.push((self.value_for_key(key), n));
+        }
+
+        points
+    }
+
+    /// Maps a positive value to its bucket index `k = ceil(log(v)/log(gamma))`
+    fn key(&self, value: f64) -> i32 {
+        (value.ln() / self.gamma.ln()).ceil() as i32
+    }
+
+    /// Returns the representative value for a bucket index:
+    /// `2*gamma^k/(gamma+1)`
+    fn value_for_key(&self, key: i32) -> f64 {
+        2.0 * self.gamma.powi(key) / (self.gamma + 1.0)
+    }
+
+    fn add_to_bin(bins: &mut Vec<(i32, u32)>, key: i32, n: u32) {
+        match bins.binary_search_by_key(&key, |&(k, _)| k) {
+            Ok(idx) => bins[idx].1 += n,
+            Err(idx) => bins.insert(idx, (key, n)),
+        }
+    }
+
+    /// Folds the lowest-index bins into their neighbor until the bin count
+    /// is back within `max_bins`
+    fn collapse(bins: &mut Vec<(i32, u32)>, max_bins: usize) {
+        while bins.len() > max_bins {
+            let (_, lowest_count) = bins.remove(0);
+            match bins.first_mut() {
+                Some(neighbor) => neighbor.1 += lowest_count,
+                None => break,
+            }
+        }
+    }
+}
+
+impl Default for DDSketch {
+    fn default() -> Self {
+        Self::new(DEFAULT_ALPHA)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantile_of_uniform_samples_is_within_relative_accuracy() {
+        let alpha = 0.01;
+        let mut sketch = DDSketch::new(alpha);
+        for v in 1..=1000 {
+            sketch.insert(v as f64);
+        }
+
+        let median = sketch.quantile(0.5).unwrap();
+        assert!((median - 500.0).abs() <= 500.0 * alpha, "median was {median}");
+        assert_eq!(sketch.count(), 1000);
+        assert_eq!(sketch.min(), Some(1.0));
+        assert_eq!(sketch.max(), Some(1000.0));
+    }
+
+    #[test]
+    fn values_within_min_value_collapse_into_zero_bucket() {
+        let mut sketch = DDSketch::new(DEFAULT_ALPHA);
+        sketch.insert(0.0);
+        sketch.insert(DEFAULT_MIN_VALUE / 2.0);
+
+        assert_eq!(sketch.quantile(0.0), Some(0.0));
+        assert_eq!(sketch.to_weighted_points(), vec![(0.0, 2)]);
+    }
+
+    #[test]
+    fn to_weighted_points_does_not_expand_per_sample() {
+        let mut sketch = DDSketch::new(DEFAULT_ALPHA);
+        for _ in 0..1_000_000 {
+            sketch.insert(42.0);
+        }
+
+        let points = sketch.to_weighted_points();
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].1, 1_000_000);
+    }
+
+    #[test]
+    fn merge_combines_counts_from_another_sketch() {
+        let mut a = DDSketch::new(DEFAULT_ALPHA);
+        a.insert(10.0);
+        let mut b = DDSketch::new(DEFAULT_ALPHA);
+        b.insert(20.0);
+
+        a.merge(&b);
+
+        assert_eq!(a.count(), 2);
+        assert_eq!(a.min(), Some(10.0));
+        assert_eq!(a.max(), Some(20.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "different alpha")]
+    fn merge_panics_on_mismatched_alpha() {
+        let mut a = DDSketch::new(0.01);
+        let b = DDSketch::new(0.02);
+        a.merge(&b);
+    }
+}
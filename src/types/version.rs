@@ -0,0 +1,11 @@
+//! API Version Types
+
+/// Supported Datadog API versions
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ApiVersion {
+    /// Datadog API v1
+    V1,
+    /// Datadog API v2
+    #[default]
+    V2,
+}